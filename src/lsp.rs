@@ -0,0 +1,231 @@
+//! `--lsp` mode: speaks the Language Server Protocol over stdio instead of serving HTTP,
+//! publishing spell-check diagnostics and quick-fix suggestions for a single language.
+
+use divvunspell::{speller::Speller, tokenizer::Tokenize};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+use tower_lsp::jsonrpc::Result;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{async_trait, Client, LanguageServer, LspService, Server};
+
+type SpellerHandle = Arc<dyn Speller + Send + Sync>;
+
+struct Backend {
+    client: Client,
+    speller: SpellerHandle,
+    documents: RwLock<HashMap<Url, String>>,
+}
+
+impl Backend {
+    /// Re-tokenizes `text` and publishes one diagnostic per misspelled word.
+    async fn check(&self, uri: Url, text: &str) {
+        let mut diagnostics = vec![];
+
+        for (start, word) in text.word_indices() {
+            let owned = word.to_string();
+            if self.speller.clone().is_correct(&owned) {
+                continue;
+            }
+
+            let range = Range::new(
+                offset_to_position(text, start),
+                offset_to_position(text, start + word.len()),
+            );
+            diagnostics.push(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::WARNING),
+                source: Some("divvun-speller".to_string()),
+                message: format!("Possible spelling mistake: \"{word}\""),
+                ..Default::default()
+            });
+        }
+
+        self.client
+            .publish_diagnostics(uri, diagnostics, None)
+            .await;
+    }
+}
+
+/// Converts a UTF-8 byte offset into an LSP `Position` (0-based line, UTF-16 character).
+fn offset_to_position(text: &str, offset: usize) -> Position {
+    let prefix = &text[..offset];
+    let line = prefix.matches('\n').count();
+    let line_start = prefix.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let character = text[line_start..offset].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
+
+/// The inverse of [`offset_to_position`]: converts an LSP `Position` back into a UTF-8 byte
+/// offset into `text`, or `None` if the position falls outside of it.
+fn position_to_offset(text: &str, position: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        line_start = text[line_start..].find('\n').map(|i| line_start + i + 1)?;
+    }
+
+    let line_end = text[line_start..]
+        .find('\n')
+        .map(|i| line_start + i)
+        .unwrap_or(text.len());
+    let line = &text[line_start..line_end];
+
+    let mut utf16_count = 0;
+    for (byte_idx, c) in line.char_indices() {
+        if utf16_count == position.character {
+            return Some(line_start + byte_idx);
+        }
+        utf16_count += c.len_utf16() as u32;
+    }
+
+    (utf16_count == position.character).then_some(line_end)
+}
+
+#[async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "divvun-worker-speller".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::INFO, "divvun-worker-speller LSP ready")
+            .await;
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let uri = params.text_document.uri;
+        let text = params.text_document.text;
+        self.documents.write().await.insert(uri.clone(), text.clone());
+        self.check(uri, &text).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        let Some(change) = params.content_changes.into_iter().last() else {
+            return;
+        };
+        let uri = params.text_document.uri;
+        self.documents
+            .write()
+            .await
+            .insert(uri.clone(), change.text.clone());
+        self.check(uri, &change.text).await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents.write().await.remove(&params.text_document.uri);
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let documents = self.documents.read().await;
+        let Some(text) = documents.get(&uri) else {
+            return Ok(None);
+        };
+
+        // The client is expected to echo back the diagnostics it already has for this range
+        // (per the LSP spec) rather than us re-deriving "the word under the cursor" from a
+        // possibly zero-width selection range.
+        let actions = params
+            .context
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                let word_range = diagnostic.range;
+                let start = position_to_offset(text, word_range.start)?;
+                let end = position_to_offset(text, word_range.end)?;
+                let word = text.get(start..end)?;
+                (!self.speller.clone().is_correct(word)).then(|| (word_range, word.to_string()))
+            })
+            .flat_map(|(word_range, word)| {
+                self.speller
+                    .clone()
+                    .suggest(&word)
+                    .into_iter()
+                    .map(move |s| (word_range, s.value().to_owned()))
+            })
+            .map(|(word_range, suggestion)| {
+                let edit = TextEdit::new(word_range, suggestion.clone());
+                let mut changes = HashMap::new();
+                changes.insert(uri.clone(), vec![edit]);
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Replace with \"{suggestion}\""),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(Some(actions))
+    }
+}
+
+/// Runs the LSP server over stdio for a single `speller`, until the client disconnects.
+pub async fn run(speller: SpellerHandle) -> anyhow::Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+
+    let (service, socket) = LspService::new(|client| Backend {
+        client,
+        speller,
+        documents: RwLock::new(HashMap::new()),
+    });
+
+    Server::new(stdin, stdout, socket).serve(service).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_to_position_is_zero_based_and_resets_per_line() {
+        let text = "foo\nbar baz";
+        assert_eq!(offset_to_position(text, 0), Position::new(0, 0));
+        assert_eq!(offset_to_position(text, 4), Position::new(1, 0));
+        assert_eq!(offset_to_position(text, 8), Position::new(1, 4));
+    }
+
+    #[test]
+    fn offset_to_position_counts_utf16_units_not_bytes() {
+        // "é" is 2 bytes in UTF-8 but 1 unit in UTF-16.
+        let text = "é ok";
+        assert_eq!(offset_to_position(text, 3), Position::new(0, 2));
+    }
+
+    #[test]
+    fn position_to_offset_is_the_inverse_of_offset_to_position() {
+        let text = "foo\nbar baz\nqux";
+        for offset in [0, 3, 4, 8, 11, 12, 15] {
+            let position = offset_to_position(text, offset);
+            assert_eq!(position_to_offset(text, position), Some(offset));
+        }
+    }
+
+    #[test]
+    fn position_to_offset_rejects_out_of_range_positions() {
+        let text = "foo";
+        assert_eq!(position_to_offset(text, Position::new(5, 0)), None);
+    }
+}